@@ -0,0 +1,74 @@
+//! Persistent network configuration, loaded once at startup from the user's config
+//! directory. When absent, `/setup` serves a first-run wizard (see `server.rs`) that
+//! collects it so returning users aren't stuck re-entering a room code every launch.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::easytier::argument::Argument;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PublicServer {
+    pub name: String,
+    pub uri: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Config {
+    pub network_name: String,
+    pub network_secret: String,
+    pub relays: Vec<PublicServer>,
+    #[serde(default)]
+    pub compression: bool,
+    #[serde(default)]
+    pub enable_kcp_proxy: bool,
+    #[serde(default)]
+    pub p2p_only: bool,
+}
+
+impl Config {
+    pub fn to_arguments(&self) -> Vec<Argument> {
+        let mut arguments = vec![
+            Argument::NetworkName(self.network_name.clone().into()),
+            Argument::NetworkSecret(self.network_secret.clone().into()),
+        ];
+
+        for relay in &self.relays {
+            arguments.push(Argument::PublicServer(relay.uri.clone().into()));
+        }
+        if self.compression {
+            arguments.push(Argument::Compression("zstd".into()));
+        }
+        if self.enable_kcp_proxy {
+            arguments.push(Argument::EnableKcpProxy);
+        }
+        if self.p2p_only {
+            arguments.push(Argument::P2POnly);
+        }
+
+        return arguments;
+    }
+}
+
+fn path() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("terracotta")
+        .join("config.toml")
+}
+
+pub fn load() -> Option<Config> {
+    fs::read_to_string(path()).ok().and_then(|content| toml::from_str(&content).ok())
+}
+
+pub fn save(config: &Config) {
+    let file = path();
+    if let Some(parent) = file.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+
+    if let Ok(content) = toml::to_string_pretty(config) {
+        let _ = fs::write(file, content);
+    }
+}
@@ -5,14 +5,24 @@ type CowString = Cow<'static, str>;
 
 #[derive(Clone)]
 pub enum Proto {
-    TCP, UDP
+    TCP, UDP,
+    /// WebSocket over TCP; traverses restrictive NATs and HTTP-only egress (e.g. corporate
+    /// proxies) far better than raw TCP.
+    Ws,
+    /// WebSocket over TLS, for egress paths that only allow HTTPS.
+    Wss,
+    /// QUIC/HTTP3, UDP-based.
+    Quic,
 }
 
 impl Proto {
     pub fn name(&self) -> &'static str {
         match self {
             Proto::TCP => "tcp",
-            Proto::UDP => "udp"
+            Proto::UDP => "udp",
+            Proto::Ws => "ws",
+            Proto::Wss => "wss",
+            Proto::Quic => "quic",
         }
     }
 }
@@ -24,6 +34,7 @@ pub enum Argument {
     MultiThread,
     LatencyFirst,
     EnableKcpProxy,
+    P2POnly,
     NetworkName(CowString),
     NetworkSecret(CowString),
     PublicServer(CowString),
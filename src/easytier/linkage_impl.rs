@@ -1,15 +1,17 @@
+use crate::config::PublicServer;
 use crate::easytier::argument::{Argument, PortForward, Proto};
+use crate::relay;
 use easytier::common::config::{ConfigFileControl, TomlConfigLoader};
 use easytier::launcher::NetworkInstance;
 use easytier::proto::api::instance::{ListRouteRequest, Route, ShowNodeInfoRequest};
 use easytier::proto::rpc_types::controller::BaseController;
 use std::cell::UnsafeCell;
-use std::net::Ipv4Addr;
+use std::net::{IpAddr, Ipv4Addr};
 use std::sync::{Arc, RwLock};
 use std::thread;
 use std::time::Duration;
-use easytier::proto::api::config::{ConfigPatchAction, InstanceConfigPatch, PatchConfigRequest, PortForwardPatch};
-use easytier::proto::common::{PortForwardConfigPb, SocketType};
+use easytier::proto::api::config::{ConfigPatchAction, InstanceConfigPatch, PatchConfigRequest, PeerConfigPatch, PortForwardPatch};
+use easytier::proto::common::{PeerConfigPb, PortForwardConfigPb, SocketType};
 use tokio::runtime::Runtime;
 use toml::{Table, Value};
 
@@ -18,7 +20,7 @@ lazy_static::lazy_static! {
 }
 
 pub struct EasyTierTunRequest {
-    pub address: Ipv4Addr,
+    pub address: IpAddr,
     pub network_length: u8,
     pub cidrs: Vec<String>,
     pub dest: Arc<RwLock<Option<i32>>>,
@@ -31,6 +33,10 @@ pub struct Easytier(Option<EasyTierHolder>);
 struct EasyTierHolder {
     instance: NetworkInstance,
     runtime: Runtime,
+    // Set by the background `relay::select` prober whenever it decides to migrate; drained
+    // and applied by `poll_relay_migration`, which must only be called from a plain thread
+    // (same `block_on`-reentrancy constraint as `get_players`).
+    relay_migration: Arc<RwLock<Option<PublicServer>>>,
 }
 
 fn create() -> EasytierFactory {
@@ -67,6 +73,16 @@ impl EasytierFactory {
         acquire_table().insert("udp_whitelist".into(), Value::Array(vec![]));
         let udp_whitelist = || acquire_table().get_mut("udp_whitelist").unwrap().as_array_mut().unwrap();
 
+        let relay_candidates: Vec<PublicServer> = args.iter()
+            .filter_map(|arg| match arg {
+                Argument::PublicServer(uri) => Some(PublicServer {
+                    name: uri.to_string(),
+                    uri: uri.to_string(),
+                }),
+                _ => None,
+            })
+            .collect();
+
         for arg in args {
             match arg {
                 Argument::NoTun => {
@@ -186,14 +202,21 @@ impl EasytierFactory {
             let mut p_proxy_cidrs = vec![];
 
             loop {
-                let address = service.get_peer_manage_service()
+                let node_info = service.get_peer_manage_service()
                     .show_node_info(BaseController::default(), ShowNodeInfoRequest::default())
                     .await.ok()
                     .and_then(|my_info| my_info.node_info)
-                    .unwrap()
-                    .ipv4_addr
+                    .unwrap();
+
+                // Prefer the existing IPv4 overlay address when the node has one, to avoid
+                // silently flipping an already-working dual-stack node over to IPv6.
+                let address = node_info.ipv4_addr
                     .parse::<cidr::Ipv4Inet>().ok()
-                    .map(|address| { (address.address(), address.network_length()) });
+                    .map(|address| (IpAddr::V4(address.address()), address.network_length()))
+                    .or_else(|| node_info.ipv6_addr
+                        .parse::<cidr::Ipv6Inet>().ok()
+                        .map(|address| (IpAddr::V6(address.address()), address.network_length()))
+                    );
 
                 let proxy_cidrs = service.get_peer_manage_service()
                     .list_route(BaseController::default(), ListRouteRequest::default())
@@ -220,7 +243,15 @@ impl EasytierFactory {
             }
         });
 
-        Easytier(Some(EasyTierHolder { instance, runtime }))
+        let relay_migration = Arc::new(RwLock::new(None));
+        if relay_candidates.len() > 1 {
+            let target = relay_migration.clone();
+            relay::select(relay_candidates, move |best| {
+                *target.write().unwrap() = Some(best);
+            });
+        }
+
+        Easytier(Some(EasyTierHolder { instance, runtime, relay_migration }))
     }
 }
 
@@ -250,6 +281,40 @@ impl Easytier {
             })
     }
 
+    /// Applies a relay migration decided by the background `relay::select` prober, if one
+    /// is pending. Must only be called from a plain thread (the same `block_on`-reentrancy
+    /// constraint as [`Easytier::get_players`]), never from Rocket's async handlers.
+    pub fn poll_relay_migration(&mut self) -> bool {
+        let Some(holder) = self.0.as_ref() else { return false; };
+        let Some(target) = holder.relay_migration.write().unwrap().take() else { return false; };
+
+        let service = holder.instance.get_api_service().unwrap();
+        let task = service.get_config_service()
+            .patch_config(BaseController::default(), PatchConfigRequest {
+                patch: Some(InstanceConfigPatch {
+                    peers: vec![PeerConfigPatch {
+                        action: ConfigPatchAction::Add as i32,
+                        cfg: Some(PeerConfigPb {
+                            uri: target.uri.clone(),
+                        }),
+                    }],
+                    ..Default::default()
+                }),
+                ..Default::default()
+            });
+
+        return match holder.runtime.block_on(task) {
+            Ok(_) => {
+                logging!("EasyTier", "Migrated to relay {} ({}).", target.name, target.uri);
+                true
+            }
+            Err(e) => {
+                logging!("EasyTier", "Cannot migrate to relay {}: {:?}", target.name, e);
+                false
+            }
+        };
+    }
+
     pub fn add_port_forward(
         &mut self,
         forwards: &[PortForward],
@@ -265,8 +330,10 @@ impl Easytier {
                                 bind_addr: Some(forward.local.into()),
                                 dst_addr: Some(forward.remote.into()),
                                 socket_type: match forward.proto {
-                                    Proto::TCP => SocketType::Tcp,
-                                    Proto::UDP => SocketType::Udp,
+                                    // Ws/Wss ride over an underlying TCP stream, Quic over UDP;
+                                    // port-forward config only cares about the transport socket type.
+                                    Proto::TCP | Proto::Ws | Proto::Wss => SocketType::Tcp,
+                                    Proto::UDP | Proto::Quic => SocketType::Udp,
                                 } as i32,
                             }),
                         }).collect::<Vec<PortForwardPatch>>(),
@@ -1,11 +1,15 @@
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 use std::io::Result;
-use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, UdpSocket};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6, UdpSocket};
 use std::sync::mpsc::{self, Receiver, Sender};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+/// How long an announcer can go quiet before [`relay`] stops re-advertising its world.
+const DISCOVERY_TTL: Duration = Duration::from_secs(5);
 
 pub struct FakeServer {
-    pub port: u16,
     signal: Sender<Signals>,
 }
 
@@ -13,30 +17,207 @@ impl FakeServer {
     pub fn activate(&self) {
         let _ = self.signal.send(Signals::Activate);
     }
+
+    /// Advertises (or re-advertises with a new MOTD/port) the room under `room_id`.
+    pub fn update(&self, room_id: u32, motd: String, port: u16) {
+        let _ = self.signal.send(Signals::Update { room_id, motd, port });
+    }
+
+    pub fn remove(&self, room_id: u32) {
+        let _ = self.signal.send(Signals::Remove { room_id });
+    }
 }
 
 enum Signals {
-    Activate, Terminate
+    Activate,
+    Terminate,
+    Update { room_id: u32, motd: String, port: u16 },
+    Remove { room_id: u32 },
+}
+
+/// A LAN world announced by a vanilla Minecraft client via the `[MOTD]`/`[AD]` protocol.
+pub struct DiscoveredWorld {
+    pub motd: String,
+    pub port: u16,
+    pub source_ip: IpAddr,
+}
+
+pub struct DiscoveryListener {
+    events: Receiver<DiscoveredWorld>,
+    signal: Sender<Signals>,
+}
+
+impl DiscoveryListener {
+    pub fn events(&self) -> &Receiver<DiscoveredWorld> {
+        &self.events
+    }
+}
+
+impl Drop for DiscoveryListener {
+    fn drop(&mut self) {
+        let _ = self.signal.send(Signals::Terminate);
+    }
+}
+
+/// Joins the Minecraft LAN multicast group on every local interface and reports
+/// hosts who click "Open to LAN" so they can be re-advertised on the virtual network.
+pub fn listen() -> DiscoveryListener {
+    let (signal_tx, signal_rx) = mpsc::channel::<Signals>();
+    let (event_tx, event_rx) = mpsc::channel::<DiscoveredWorld>();
+    thread::spawn(move || run_listener(signal_rx, event_tx));
+
+    return DiscoveryListener { events: event_rx, signal: signal_tx };
+}
+
+fn run_listener(signal: Receiver<Signals>, events: Sender<DiscoveredWorld>) {
+    let sockets: Vec<UdpSocket> = crate::ADDRESSES
+        .iter()
+        .map(|address| {
+            let domain = match address {
+                IpAddr::V4(_) => socket2::Domain::IPV4,
+                IpAddr::V6(_) => socket2::Domain::IPV6,
+            };
+            let socket = socket2::Socket::new(domain, socket2::Type::DGRAM, None)?;
+
+            // The vanilla Minecraft client is already listening on 4445, and every interface
+            // below binds the same 0.0.0.0:4445/[::]:4445 address; without SO_REUSEADDR (and
+            // SO_REUSEPORT where available) only the first bind would succeed.
+            socket.set_reuse_address(true)?;
+            #[cfg(not(target_os = "windows"))]
+            socket.set_reuse_port(true)?;
+
+            socket.bind(&match address {
+                IpAddr::V4(_) => SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 4445)),
+                IpAddr::V6(_) => SocketAddr::V6(SocketAddrV6::new(Ipv6Addr::UNSPECIFIED, 4445, 0, 0)),
+            }.into())?;
+            socket.set_read_timeout(Some(Duration::from_millis(200)))?;
+
+            match address {
+                IpAddr::V4(iface) => {
+                    socket.join_multicast_v4(&Ipv4Addr::new(224, 0, 2, 60), iface)?;
+                }
+                IpAddr::V6(_) => {
+                    socket.join_multicast_v6(&Ipv6Addr::new(0xFF75, 0x230, 0, 0, 0, 0, 0, 0x60), 0)?;
+                }
+            }
+
+            return Ok(UdpSocket::from(socket));
+        })
+        .filter_map(|r: Result<UdpSocket>| r.ok())
+        .collect();
+
+    let mut buffer = [0u8; 1500];
+    loop {
+        if let Ok(Signals::Terminate) = signal.try_recv() {
+            break;
+        }
+
+        if sockets.is_empty() {
+            thread::sleep(Duration::from_millis(200));
+            continue;
+        }
+
+        for socket in sockets.iter() {
+            if let Ok((size, source)) = socket.recv_from(&mut buffer)
+                && let Some(world) = parse_announcement(&buffer[..size], source.ip())
+            {
+                let _ = events.send(world);
+            }
+        }
+    }
+}
+
+/// Tolerantly parses `[MOTD]{motd}[/MOTD][AD]{port}[/AD]`, ignoring malformed packets.
+fn parse_announcement(packet: &[u8], source_ip: IpAddr) -> Option<DiscoveredWorld> {
+    let text = String::from_utf8_lossy(packet);
+
+    let motd = extract_between(&text, "[MOTD]", "[/MOTD]")?;
+    let port = extract_between(&text, "[AD]", "[/AD]")?
+        .trim()
+        .parse::<u32>()
+        .ok()?
+        .min(u16::MAX as u32) as u16;
+
+    return Some(DiscoveredWorld { motd: motd.to_string(), port, source_ip });
+}
+
+fn extract_between<'a>(text: &'a str, begin: &str, end: &str) -> Option<&'a str> {
+    let begin_index = text.find(begin)? + begin.len();
+    let end_index = begin_index + text[begin_index..].find(end)?;
+
+    return Some(&text[begin_index..end_index]);
+}
+
+/// Bridges LAN "Open to LAN" worlds detected by [`listen`] back onto the virtual network via
+/// `server`, so a peer's locally-hosted world becomes visible to everyone else in the room.
+/// Each distinct announcer gets a stable room id; one that goes quiet for longer than
+/// [`DISCOVERY_TTL`] is dropped again.
+pub fn relay(listener: DiscoveryListener, server: FakeServer) {
+    thread::spawn(move || {
+        let mut last_seen: HashMap<IpAddr, Instant> = HashMap::new();
+
+        loop {
+            match listener.events().recv_timeout(DISCOVERY_TTL) {
+                Ok(world) => {
+                    last_seen.insert(world.source_ip, Instant::now());
+                    server.update(room_id_for(world.source_ip), world.motd, world.port);
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+
+            last_seen.retain(|ip, seen| {
+                if seen.elapsed() < DISCOVERY_TTL {
+                    return true;
+                }
+
+                server.remove(room_id_for(*ip));
+                return false;
+            });
+        }
+    });
 }
 
-pub fn create(port: u16, motd: &'static str) -> FakeServer {
+/// Derives a stable room id for an announcing IP, independent of the ids `code` hands out
+/// for the locally-hosted room.
+fn room_id_for(ip: IpAddr) -> u32 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    ip.hash(&mut hasher);
+    return (hasher.finish() as u32) | 0x8000_0000;
+}
+
+pub fn create() -> FakeServer {
     let (tx, rx) = mpsc::channel::<Signals>();
-    thread::spawn(move || run(port, motd, rx));
+    thread::spawn(move || run(rx));
 
-    return FakeServer { port: port, signal: tx };
+    return FakeServer { signal: tx };
 }
 
-fn run(port: u16, motd: &'static str, signal: Receiver<Signals>) {
+fn run(signal: Receiver<Signals>) {
     let sockets: Vec<(UdpSocket, &'static SocketAddr)> = crate::ADDRESSES
         .iter()
         .map(|address| {
-            let socket = UdpSocket::bind((address.clone(), 0))?;
+            let socket = socket2::Socket::new(
+                match address {
+                    IpAddr::V4(_) => socket2::Domain::IPV4,
+                    IpAddr::V6(_) => socket2::Domain::IPV6,
+                },
+                socket2::Type::DGRAM,
+                None,
+            )?;
+            socket.bind(&SocketAddr::new(address.clone(), 0).into())?;
+
             let ip: &SocketAddr = match address {
-                IpAddr::V4(_) => {
+                IpAddr::V4(iface) => {
                     socket.set_broadcast(true)?;
                     socket.set_multicast_ttl_v4(4)?;
                     socket.set_multicast_loop_v4(true)?;
 
+                    // Pin egress to this NIC so multi-homed hosts (Wi-Fi + EasyTier tun + Ethernet)
+                    // don't leak the advertisement onto the wrong interface.
+                    socket.set_multicast_if_v4(iface)?;
+                    socket.join_multicast_v4(&Ipv4Addr::new(224, 0, 2, 60), iface)?;
+
                     lazy_static::lazy_static! {
                         static ref ADDR: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(224, 0, 2, 60)), 4445);
                     }
@@ -46,6 +227,10 @@ fn run(port: u16, motd: &'static str, signal: Receiver<Signals>) {
                 IpAddr::V6(_) => {
                     socket.set_multicast_loop_v6(true)?;
 
+                    // ADDRESSES only tracks the interface's IpAddr, not its scope id, so the
+                    // egress interface can't be pinned for IPv6; fall back to the default route.
+                    socket.join_multicast_v6(&Ipv6Addr::new(0xFF75, 0x230, 0, 0, 0, 0, 0, 0x60), 0)?;
+
                     lazy_static::lazy_static! {
                         static ref ADDR: SocketAddr = SocketAddr::new(IpAddr::V6(Ipv6Addr::new(0xFF75, 0x230, 0, 0, 0, 0, 0, 0x60)), 4445);
                     }
@@ -54,32 +239,42 @@ fn run(port: u16, motd: &'static str, signal: Receiver<Signals>) {
                 }
             };
 
-            return Ok((socket, ip));
+            return Ok((UdpSocket::from(socket), ip));
         })
         .filter_map(|r: Result<(UdpSocket, &SocketAddr)>| match r {
-            Ok(value) => Some(value), 
+            Ok(value) => Some(value),
             Err(_) => None
         })
         .collect();
 
-    match signal.recv().unwrap() {
-        Signals::Activate => {
-            let message: String = format!("[MOTD]{}[/MOTD][AD]{}[/AD]", motd, port);
-            let message_bytes = message.as_bytes();
+    let mut rooms: HashMap<u32, (String, u16)> = HashMap::new();
+    let mut active = false;
 
-            loop {
-                if let Ok(signal) = signal.try_recv() && let Signals::Terminate = signal {
-                    break;
-                }
+    loop {
+        match signal.recv_timeout(Duration::from_millis(1500)) {
+            Ok(Signals::Activate) => active = true,
+            Ok(Signals::Terminate) => break,
+            Ok(Signals::Update { room_id, motd, port }) => {
+                rooms.insert(room_id, (motd, port));
+            }
+            Ok(Signals::Remove { room_id }) => {
+                rooms.remove(&room_id);
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
 
-                for (socket, address) in sockets.iter() {
-                    let _ = socket.send_to(message_bytes, address);
-                }
+        if !active {
+            continue;
+        }
 
-                thread::sleep(Duration::from_millis(1500));
+        for (motd, port) in rooms.values() {
+            let message = format!("[MOTD]{}[/MOTD][AD]{}[/AD]", motd, port);
+            let message_bytes = message.as_bytes();
+
+            for (socket, address) in sockets.iter() {
+                let _ = socket.send_to(message_bytes, address);
             }
-        },
-        Signals::Terminate => {
         }
     }
 }
@@ -89,3 +284,53 @@ impl Drop for FakeServer {
         let _ = self.signal.send(Signals::Terminate);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source() -> IpAddr {
+        IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1))
+    }
+
+    #[test]
+    fn parses_well_formed_announcement() {
+        let world = parse_announcement(b"[MOTD]My World[/MOTD][AD]25565[/AD]", source()).unwrap();
+        assert_eq!(world.motd, "My World");
+        assert_eq!(world.port, 25565);
+    }
+
+    #[test]
+    fn parses_with_leading_and_trailing_noise() {
+        let world = parse_announcement(b"garbage[MOTD]Hi[/MOTD]junk[AD] 4567 [/AD]trailer", source()).unwrap();
+        assert_eq!(world.motd, "Hi");
+        assert_eq!(world.port, 4567);
+    }
+
+    #[test]
+    fn clamps_out_of_range_port() {
+        let world = parse_announcement(b"[MOTD]Big[/MOTD][AD]99999999[/AD]", source()).unwrap();
+        assert_eq!(world.port, u16::MAX);
+    }
+
+    #[test]
+    fn rejects_missing_motd_tag() {
+        assert!(parse_announcement(b"[AD]25565[/AD]", source()).is_none());
+    }
+
+    #[test]
+    fn rejects_missing_ad_tag() {
+        assert!(parse_announcement(b"[MOTD]My World[/MOTD]", source()).is_none());
+    }
+
+    #[test]
+    fn rejects_non_numeric_port() {
+        assert!(parse_announcement(b"[MOTD]My World[/MOTD][AD]oops[/AD]", source()).is_none());
+    }
+
+    #[test]
+    fn does_not_panic_on_invalid_utf8() {
+        let world = parse_announcement(b"[MOTD]\xff\xfe[/MOTD][AD]25565[/AD]", source()).unwrap();
+        assert_eq!(world.port, 25565);
+    }
+}
@@ -25,7 +25,7 @@ use crate::controller::Room;
 use chrono::{FixedOffset, TimeZone, Utc};
 use jni::objects::JClass;
 use jni::signature::{Primitive, ReturnType};
-use jni::sys::{jclass, jshort, jsize, jvalue, JavaVM};
+use jni::sys::{jbyte, jclass, jshort, jsize, jvalue, JavaVM};
 use jni::{objects::JString, sys::{jboolean, jint, jobject, JNI_FALSE, JNI_TRUE}, JNIEnv};
 use libc::{c_char, c_int};
 use std::time::Duration;
@@ -35,7 +35,9 @@ use std::{
 
 pub mod controller;
 mod easytier;
+mod persistence;
 mod scaffolding;
+mod sysreq;
 pub const MOTD: &'static str = "§6§l双击进入陶瓦联机大厅（请保持陶瓦运行）";
 
 mod mc;
@@ -141,6 +143,11 @@ extern "system" fn Java_net_burningtnt_terracotta_TerracottaAndroidAPI_start0(en
         env!("CARGO_CFG_TARGET_ENV"),
     );
 
+    if let Err(e) = sysreq::get_os().check() {
+        logging!("UI", "System requirement check failed: {}", e);
+        return 1;
+    }
+
     if let Err(e) = tokio::runtime::Builder::new_multi_thread()
         .enable_all()
         .build() {
@@ -156,7 +163,7 @@ extern "system" fn Java_net_burningtnt_terracotta_TerracottaAndroidAPI_start0(en
         let mut jenv = jvm.attach_current_thread_as_daemon().unwrap();
 
         let on_vpn_service_sc = jenv.get_static_method_id(
-            &clazz, "onVpnServiceStateChanged", "(BBBBSLjava/lang/String;)I"
+            &clazz, "onVpnServiceStateChanged", "(B[BSLjava/lang/String;)I"
         ).unwrap();
 
         loop {
@@ -168,13 +175,20 @@ extern "system" fn Java_net_burningtnt_terracotta_TerracottaAndroidAPI_start0(en
                 continue;
             };
 
-            let [ip1, ip2, ip3, ip4] = cfg.address.octets().map(|i| i as i8);
+            // family follows the JNI side's expectation: 4 for IPv4, 6 for IPv6.
+            let (family, address_bytes): (jbyte, Vec<i8>) = match cfg.address {
+                IpAddr::V4(ip) => (4, ip.octets().map(|i| i as i8).to_vec()),
+                IpAddr::V6(ip) => (6, ip.octets().map(|i| i as i8).to_vec()),
+            };
+            let address_array = jenv.new_byte_array(address_bytes.len() as jsize).unwrap();
+            jenv.set_byte_array_region(&address_array, 0, &address_bytes).unwrap();
+
             let cidrs = cfg.cidrs.join("\0");
             let cidrs2 = jenv.new_string(cidrs).unwrap();
 
             let tun_fd = unsafe {
                 jenv.call_static_method_unchecked(&clazz, on_vpn_service_sc, ReturnType::Primitive(Primitive::Int), &[
-                    jvalue { b: ip1 }, jvalue { b: ip2 }, jvalue { b: ip3 }, jvalue { b: ip4 },
+                    jvalue { b: family }, jvalue { l: address_array.into_raw() },
                     jvalue { s: cfg.network_length as jshort },
                     jvalue { l: cidrs2.into_raw() }
                 ])
@@ -214,6 +228,13 @@ extern "system" fn Java_net_burningtnt_terracotta_TerracottaAndroidAPI_getState0
     env.new_string(serde_json::to_string(&controller::get_state()).unwrap()).unwrap().into_raw()
 }
 
+#[unsafe(no_mangle)]
+#[allow(non_snake_case)]
+extern "system" fn Java_net_burningtnt_terracotta_TerracottaAndroidAPI_getRecentRooms0(env: JNIRawEnv, _: jclass) -> jobject {
+    let env = unsafe { JNIEnv::from_raw(env) }.unwrap();
+    env.new_string(serde_json::to_string(&persistence::known_rooms()).unwrap()).unwrap().into_raw()
+}
+
 #[unsafe(no_mangle)]
 #[allow(non_snake_case)]
 extern "system" fn Java_net_burningtnt_terracotta_TerracottaAndroidAPI_setWaiting0(_env: JNIRawEnv, _: jclass) {
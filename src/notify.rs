@@ -0,0 +1,20 @@
+//! Minimal `sd_notify` client so Terracotta can run under systemd/service supervisors
+//! in `--headless` mode. Only the subset service managers actually read is implemented:
+//! `READY=1`, `STATUS=...` and `STOPPING=1`, sent to `$NOTIFY_SOCKET` when present.
+
+#[cfg(unix)]
+pub fn notify(state: &str) {
+    use std::env;
+    use std::os::unix::net::UnixDatagram;
+
+    let Ok(path) = env::var("NOTIFY_SOCKET") else {
+        return;
+    };
+
+    if let Ok(socket) = UnixDatagram::unbound() {
+        let _ = socket.send_to(state.as_bytes(), &path);
+    }
+}
+
+#[cfg(not(unix))]
+pub fn notify(_state: &str) {}
@@ -0,0 +1,87 @@
+//! Disk-backed store for everything that should survive a restart: the node's own stable
+//! identity, the rooms it has joined (so they can be auto-rejoined), and the peers it has
+//! last seen in them. Lives next to [`crate::MACHINE_ID_FILE`] under [`crate::FILE_ROOT`] and
+//! is loaded once at startup, then written through on every change.
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Mutex;
+
+lazy_static! {
+    static ref STATE_FILE: std::path::PathBuf = crate::FILE_ROOT.join("state.json");
+    static ref STORE: Mutex<PersistedState> = Mutex::new(PersistedState::load());
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct RoomRecord {
+    pub room_code: String,
+    pub network_secret: String,
+    pub last_joined_millis: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct PeerRecord {
+    pub node_id: String,
+    pub display_name: String,
+    pub last_seen_millis: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+struct PersistedState {
+    node_id: Option<String>,
+    rooms: HashMap<String, RoomRecord>,
+    peers: HashMap<String, PeerRecord>,
+}
+
+impl PersistedState {
+    fn load() -> Self {
+        fs::read(&*STATE_FILE)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self) {
+        if let Ok(bytes) = serde_json::to_vec(self) {
+            let _ = fs::write(&*STATE_FILE, bytes);
+        }
+    }
+}
+
+/// Returns the node's stable identity, generating and persisting one on first use.
+pub fn node_id() -> String {
+    let mut state = STORE.lock().unwrap();
+    if let Some(node_id) = &state.node_id {
+        return node_id.clone();
+    }
+
+    let node_id = uuid::Uuid::new_v4().to_string();
+    state.node_id = Some(node_id.clone());
+    state.save();
+
+    return node_id;
+}
+
+pub fn known_rooms() -> Vec<RoomRecord> {
+    STORE.lock().unwrap().rooms.values().cloned().collect()
+}
+
+pub fn remember_room(record: RoomRecord) {
+    let mut state = STORE.lock().unwrap();
+    state.rooms.insert(record.room_code.clone(), record);
+    state.save();
+}
+
+pub fn forget_room(room_code: &str) {
+    let mut state = STORE.lock().unwrap();
+    state.rooms.remove(room_code);
+    state.save();
+}
+
+pub fn remember_peer(record: PeerRecord) {
+    let mut state = STORE.lock().unwrap();
+    state.peers.insert(record.node_id.clone(), record);
+    state.save();
+}
@@ -0,0 +1,103 @@
+//! Latency-based automatic selection among the configured public relays.
+//!
+//! Each candidate is TCP-probed a few times per round; a per-relay exponentially-weighted
+//! moving average smooths out noisy samples, and the lowest-EWMA reachable relay is picked.
+//! Re-probing continues in the background so hosts/guests migrate to faster infrastructure
+//! without manual intervention.
+
+use std::collections::HashMap;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::{Arc, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::config::PublicServer;
+
+const PROBES_PER_ROUND: u32 = 3;
+const PROBE_TIMEOUT: Duration = Duration::from_millis(800);
+const EWMA_ALPHA: f64 = 0.3;
+const REPROBE_INTERVAL: Duration = Duration::from_secs(30);
+const HYSTERESIS_MARGIN_MILLIS: f64 = 50.0;
+
+pub struct RelaySelector {
+    current: Arc<RwLock<Option<PublicServer>>>,
+}
+
+impl RelaySelector {
+    pub fn current(&self) -> Option<PublicServer> {
+        self.current.read().unwrap().clone()
+    }
+}
+
+/// Starts probing `candidates` in the background and calls `on_migrate` whenever a
+/// relay beats the active one by more than the hysteresis margin.
+pub fn select(candidates: Vec<PublicServer>, on_migrate: impl Fn(PublicServer) + Send + 'static) -> RelaySelector {
+    let current = Arc::new(RwLock::new(None));
+    let selector = RelaySelector { current: current.clone() };
+
+    thread::spawn(move || {
+        let mut ewma: HashMap<String, f64> = HashMap::new();
+
+        loop {
+            for candidate in &candidates {
+                if let Some(sample) = probe(candidate) {
+                    let previous = ewma.get(&candidate.uri).copied().unwrap_or(sample);
+                    ewma.insert(candidate.uri.clone(), EWMA_ALPHA * sample + (1.0 - EWMA_ALPHA) * previous);
+                }
+            }
+
+            let best = candidates.iter()
+                .filter_map(|candidate| ewma.get(&candidate.uri).map(|latency| (candidate, *latency)))
+                .min_by(|a, b| a.1.total_cmp(&b.1));
+
+            if let Some((best, best_latency)) = best {
+                let mut guard = current.write().unwrap();
+                let should_migrate = match &*guard {
+                    None => true,
+                    Some(active) if active.uri == best.uri => false,
+                    Some(active) => {
+                        let active_latency = ewma.get(&active.uri).copied().unwrap_or(f64::MAX);
+                        active_latency - best_latency > HYSTERESIS_MARGIN_MILLIS
+                    }
+                };
+
+                if should_migrate {
+                    logging!("EasyTier", "Switching relay to {} ({:.0}ms EWMA).", best.name, best_latency);
+                    *guard = Some(best.clone());
+                    drop(guard);
+                    on_migrate(best.clone());
+                }
+            }
+
+            thread::sleep(REPROBE_INTERVAL);
+        }
+    });
+
+    return selector;
+}
+
+/// Performs up to [`PROBES_PER_ROUND`] timed TCP connects, discarding failures, and
+/// returns the average round-trip in milliseconds. `None` if the relay is unreachable.
+fn probe(candidate: &PublicServer) -> Option<f64> {
+    let address = candidate.uri
+        .split("://")
+        .last()?
+        .to_socket_addrs()
+        .ok()?
+        .next()?;
+
+    let samples: Vec<f64> = (0..PROBES_PER_ROUND)
+        .filter_map(|_| {
+            let start = Instant::now();
+            TcpStream::connect_timeout(&address, PROBE_TIMEOUT)
+                .ok()
+                .map(|_| start.elapsed().as_secs_f64() * 1000.0)
+        })
+        .collect();
+
+    if samples.is_empty() {
+        return None;
+    }
+
+    return Some(samples.iter().sum::<f64>() / samples.len() as f64);
+}
@@ -1,17 +1,31 @@
+use std::path::PathBuf;
 use std::sync::{mpsc, Mutex};
 use std::thread;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use rocket::http::Status;
 use rocket::response::content::RawHtml;
 use rocket::serde::json;
+use rocket_ws::{Message, WebSocket};
+use tokio::sync::broadcast;
 
 use crate::fakeserver::FakeServer;
 use crate::scanning::Scanning;
 use crate::easytier::Easytier;
 use crate::code::{self, Room};
+use crate::persistence::{self, RoomRecord};
 use crate::LOGGING_FILE;
 
+fn now_millis() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64
+}
+
+lazy_static::lazy_static! {
+    // Fed by the monitor thread below; `/events` subscribers get pushed a fresh state
+    // JSON every time it changes instead of having to poll `/state`.
+    static ref STATE_EVENTS: broadcast::Sender<String> = broadcast::channel(16).0;
+}
+
 enum AppState {
     Waiting {
         begin: Instant,
@@ -23,11 +37,15 @@ enum AppState {
     Hosting {
         easytier: Easytier,
         room: Room,
+        // `Easytier::get_players` blocks on its own Tokio runtime, which panics if called from
+        // inside Rocket's async handlers; the monitor thread (a plain `std::thread`) refreshes
+        // this cache instead, and the route handlers just read it.
+        players: Vec<(String, std::net::Ipv4Addr)>,
     },
     Guesting {
         easytier: Easytier,
         _entry: FakeServer,
-        _room: Room,
+        room: Room,
     },
 }
 
@@ -74,25 +92,50 @@ fn index() -> Result<RawHtml<&'static str>, Status> {
     return Ok(RawHtml(&MAIN_PAGE));
 }
 
-#[get("/state")]
-fn get_state() -> json::Json<json::Value> {
-    let v = &mut *access_state();
-    return match &v.1 {
-        AppState::Waiting { .. } => json::Json(json::json!({"state": "waiting", "index": v.0})),
-        AppState::Scanning { .. } => json::Json(json::json!({"state": "scanning", "index": v.0})),
-        AppState::Hosting { room, .. } => json::Json(json::json!({
+/// Builds the same state JSON served by `/state` and pushed over `/events`.
+fn state_json(v: &mut (u32, AppState)) -> json::Value {
+    return match &mut v.1 {
+        AppState::Waiting { .. } => json::json!({"state": "waiting", "index": v.0}),
+        AppState::Scanning { .. } => json::json!({"state": "scanning", "index": v.0}),
+        AppState::Hosting { room, players, .. } => json::json!({
             "state": "hosting",
             "index": v.0,
-            "room": room.code
-        })),
-        AppState::Guesting { .. } => json::Json(json::json!({
+            "room": room.code,
+            "players": players
+        }),
+        AppState::Guesting { .. } => json::json!({
             "state": "guesting",
             "index": v.0,
             "url": format!("127.0.0.1:{}", code::LOCAL_PORT)
-        })),
+        }),
     };
 }
 
+#[get("/state")]
+fn get_state() -> json::Json<json::Value> {
+    return json::Json(state_json(&mut *access_state()));
+}
+
+#[get("/events")]
+fn events(ws: WebSocket) -> rocket_ws::Channel<'static> {
+    let mut rx = STATE_EVENTS.subscribe();
+
+    return ws.channel(move |mut stream| Box::pin(async move {
+        let current = serde_json::to_string(&state_json(&mut *access_state())).unwrap();
+        if stream.send(Message::Text(current)).await.is_err() {
+            return Ok(());
+        }
+
+        while let Ok(message) = rx.recv().await {
+            if stream.send(Message::Text(message)).await.is_err() {
+                break;
+            }
+        }
+
+        Ok(())
+    }));
+}
+
 #[get("/state/ide")]
 fn set_state_ide() -> Status {
     logging!("UI", "Setting Server to state IDE.");
@@ -129,13 +172,19 @@ fn set_state_guesting(room: Option<String>) -> Status {
             room.code
         );
 
+        persistence::remember_room(RoomRecord {
+            room_code: room.code.clone(),
+            network_secret: room.code.clone(),
+            last_joined_millis: now_millis(),
+        });
+
         let state = &mut *access_state();
         state.0 += 1;
         let (easytier, entry) = room.start();
         state.1 = AppState::Guesting {
             easytier: easytier,
             _entry: entry.unwrap(),
-            _room: room,
+            room: room,
         };
         return Status::Ok;
     }
@@ -148,13 +197,99 @@ fn download_log() -> std::fs::File {
     return std::fs::File::open((*LOGGING_FILE).clone()).unwrap();
 }
 
-pub async fn server_main(port: mpsc::Sender<u16>) {
+#[get("/setup")]
+fn get_setup() -> json::Json<json::Value> {
+    let node_id = persistence::node_id();
+    return match crate::config::load() {
+        Some(config) => json::Json(json::json!({
+            "configured": true,
+            "node_id": node_id,
+            "network_name": config.network_name,
+            "relays": config.relays.into_iter().map(|relay| relay.name).collect::<Vec<_>>(),
+            "compression": config.compression,
+            "enable_kcp_proxy": config.enable_kcp_proxy,
+            "p2p_only": config.p2p_only,
+        })),
+        None => json::Json(json::json!({"configured": false, "node_id": node_id})),
+    };
+}
+
+#[post("/setup", data = "<body>")]
+fn post_setup(body: json::Json<crate::config::Config>) -> Status {
+    crate::config::save(&body.into_inner());
+    return Status::Ok;
+}
+
+/// Where the control server (the Rocket instance serving the UI/API) binds.
+#[derive(Clone)]
+pub enum ControlEndpoint {
+    Tcp(u16),
+    /// `path` to a Unix domain socket; embedding scenarios (Android host process, sandboxed
+    /// launchers) can drive Terracotta's state machine without opening any network-reachable port.
+    Unix(PathBuf),
+}
+
+/// What the control server actually ended up bound to, reported back to the caller.
+pub enum BoundEndpoint {
+    Tcp(u16),
+    Unix(PathBuf),
+}
+
+/// Command-line options for long-lived/headless deployments (systemd, service supervisors).
+pub struct LaunchOptions {
+    pub headless: bool,
+    pub control_endpoint: ControlEndpoint,
+}
+
+impl LaunchOptions {
+    pub fn from_args() -> Self {
+        let args: Vec<String> = std::env::args().collect();
+
+        let headless = args.iter().any(|arg| arg == "--headless");
+        let control_endpoint = args.iter()
+            .position(|arg| arg == "--listen")
+            .and_then(|index| args.get(index + 1))
+            .map(|value| match value.strip_prefix("unix:") {
+                Some(path) => ControlEndpoint::Unix(PathBuf::from(path)),
+                None => ControlEndpoint::Tcp(value.parse().unwrap_or(0)),
+            })
+            .unwrap_or(ControlEndpoint::Tcp(if cfg!(debug_assertions) { 8080 } else { 0 }));
+
+        return LaunchOptions { headless, control_endpoint };
+    }
+}
+
+fn state_name(state: &AppState) -> &'static str {
+    return match state {
+        AppState::Waiting { .. } => "WAITING",
+        AppState::Scanning { .. } => "SCANNING",
+        AppState::Hosting { .. } => "HOSTING",
+        AppState::Guesting { .. } => "GUESTING",
+    };
+}
+
+pub async fn server_main(endpoint_report: mpsc::Sender<BoundEndpoint>, options: LaunchOptions) {
+    // Bridge peers' "Open to LAN" worlds across the virtual network for the whole lifetime
+    // of the process, independent of our own hosting/guesting state.
+    let discovery_relay = crate::fakeserver::create();
+    discovery_relay.activate();
+    crate::fakeserver::relay(crate::fakeserver::listen(), discovery_relay);
+
     let (launch_signal_tx, launch_signal_rx) = mpsc::channel::<()>();
     let shutdown_signal_tx = launch_signal_tx.clone();
+    let headless = options.headless;
+
+    let control_endpoint = options.control_endpoint;
+    let fairing_endpoint = control_endpoint.clone();
+
+    let tcp_port = match &control_endpoint {
+        ControlEndpoint::Tcp(port) => *port,
+        ControlEndpoint::Unix(_) => 0,
+    };
 
     let rocket = rocket::custom(rocket::Config {
         log_level: rocket::log::LogLevel::Critical,
-        port: if cfg!(debug_assertions) { 8080 } else { 0 },
+        port: tcp_port,
         ..rocket::Config::default()
     })
     .mount(
@@ -162,19 +297,32 @@ pub async fn server_main(port: mpsc::Sender<u16>) {
         routes![
         index,
         get_state,
+        events,
         set_state_ide,
         set_state_scanning,
         set_state_guesting,
-        download_log
+        download_log,
+        get_setup,
+        post_setup
     ]
     )
-    .attach(rocket::fairing::AdHoc::on_liftoff("Open Browser", move |rocket| {
+    .attach(rocket::fairing::AdHoc::on_liftoff("Report Endpoint", move |rocket| {
         Box::pin(async move {
             launch_signal_tx.send(()).unwrap();
-            
-            let local_port = rocket.config().port;
-            let _ = open::that(format!("http://127.0.0.1:{}/", local_port));
-            let _ = port.send(local_port);
+
+            let bound = match fairing_endpoint {
+                ControlEndpoint::Tcp(_) => {
+                    let local_port = rocket.config().port;
+                    if !headless {
+                        let _ = open::that(format!("http://127.0.0.1:{}/", local_port));
+                    }
+                    BoundEndpoint::Tcp(local_port)
+                }
+                ControlEndpoint::Unix(path) => BoundEndpoint::Unix(path),
+            };
+            let _ = endpoint_report.send(bound);
+
+            crate::notify::notify("READY=1");
         })
     }))
     .ignite()
@@ -185,8 +333,14 @@ pub async fn server_main(port: mpsc::Sender<u16>) {
     std::thread::spawn(move || {
         launch_signal_rx.recv().unwrap();
 
+        let mut last_broadcast = String::new();
+
         loop {
-            fn handle_offline(time: &Instant) -> bool {
+            fn handle_offline(time: &Instant, headless: bool) -> bool {
+                if headless {
+                    return false;
+                }
+
                 const TIMEOUT: u128 = if cfg!(debug_assertions) { 3000 } else { 10000 };
 
                 let timeout = Instant::now().duration_since(*time).as_millis();
@@ -208,13 +362,15 @@ pub async fn server_main(port: mpsc::Sender<u16>) {
             let state = &mut *GLOBAL_STATE.lock().unwrap();
             match &mut state.1 {
                 AppState::Waiting { begin } => {
-                    if handle_offline(begin) {
+                    if handle_offline(begin, headless) {
+                        crate::notify::notify("STOPPING=1");
                         shutdown.notify();
                         return;
                     }
                 }
                 AppState::Scanning { begin, scanner } => {
-                    if handle_offline(begin) {
+                    if handle_offline(begin, headless) {
+                        crate::notify::notify("STOPPING=1");
                         shutdown.notify();
                         return;
                     }
@@ -229,37 +385,82 @@ pub async fn server_main(port: mpsc::Sender<u16>) {
                             room.code
                         );
 
+                        persistence::remember_room(RoomRecord {
+                            room_code: room.code.clone(),
+                            network_secret: room.code.clone(),
+                            last_joined_millis: now_millis(),
+                        });
+
                         state.0 += 1;
                         state.1 = AppState::Hosting {
                             easytier: room.start().0,
                             room: room,
+                            players: vec![],
                         };
                     }
                 }
-                AppState::Hosting { easytier, .. } => {
+                AppState::Hosting { easytier, room, players } => {
                     if !easytier.is_alive() {
                         logging!("UI", "Easytier has been dead.");
+                        persistence::forget_room(&room.code);
                         state.0 += 1;
                         state.1 = AppState::Waiting {
                             begin: Instant::now(),
                         };
+                    } else {
+                        easytier.poll_relay_migration();
+
+                        *players = easytier.get_players().unwrap_or_default();
+                        for (hostname, _) in players.iter() {
+                            persistence::remember_peer(persistence::PeerRecord {
+                                node_id: hostname.clone(),
+                                display_name: hostname.clone(),
+                                last_seen_millis: now_millis(),
+                            });
+                        }
                     }
                 }
-                AppState::Guesting { easytier, .. } => {
+                AppState::Guesting { easytier, room, .. } => {
                     if !easytier.is_alive() {
                         logging!("UI", "Easytier has been dead.");
+                        persistence::forget_room(&room.code);
                         state.0 += 1;
                         state.1 = AppState::Waiting {
                             begin: Instant::now(),
                         };
+                    } else {
+                        easytier.poll_relay_migration();
                     }
                 }
             };
 
+            let current = serde_json::to_string(&state_json(state)).unwrap();
+            if current != last_broadcast {
+                crate::notify::notify(&format!("STATUS={}", state_name(&state.1)));
+                let _ = STATE_EVENTS.send(current.clone());
+                last_broadcast = current;
+            }
+
             thread::sleep(Duration::from_millis(200));
         }
     });
 
-    let _ = rocket.launch().await;
+    match control_endpoint {
+        ControlEndpoint::Tcp(_) => {
+            let _ = rocket.launch().await;
+        }
+        ControlEndpoint::Unix(path) => {
+            // Rocket's own Unix listener (unlike `std::os::unix::net::UnixListener`) implements
+            // `rocket::listener::Bind`, and handles the stale-socket-file bind/cleanup dance.
+            match rocket::listener::unix::UnixListener::bind(&path).await {
+                Ok(listener) => {
+                    let _ = rocket.launch_on(listener).await;
+                }
+                Err(e) => logging!("UI", "Cannot bind control socket {}: {:?}", path.display(), e),
+            }
+        }
+    }
+
+    crate::notify::notify("STOPPING=1");
     let _ = shutdown_signal_tx.send(());
 }
\ No newline at end of file
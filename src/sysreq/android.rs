@@ -0,0 +1,13 @@
+use super::{RequirementError, SystemRequirement};
+
+pub struct AndroidRequirement;
+
+impl SystemRequirement for AndroidRequirement {
+    fn check(&self) -> Result<(), RequirementError> {
+        // `VpnService` itself enforces the OS-level prerequisites (API level, user consent
+        // dialog) the first time it's started, so there's nothing left to gate here today.
+        // The impl exists so `get_os()` has an Android arm and future prerequisites have
+        // a home without touching callers.
+        return Ok(());
+    }
+}
@@ -0,0 +1,20 @@
+use super::{RequirementError, SystemRequirement};
+use std::path::Path;
+
+pub struct LinuxRequirement;
+
+impl SystemRequirement for LinuxRequirement {
+    fn check(&self) -> Result<(), RequirementError> {
+        if !Path::new("/dev/net/tun").exists() {
+            return Err(RequirementError::new("SYS_TUN_NOT_AVAILABLE"));
+        }
+
+        if !caps::has_cap(None, caps::CapSet::Effective, caps::Capability::CAP_NET_ADMIN)
+            .unwrap_or(false)
+        {
+            return Err(RequirementError::new("SYS_CAP_NET_ADMIN_MISSING"));
+        }
+
+        return Ok(());
+    }
+}
@@ -0,0 +1,76 @@
+//! Cross-platform compatibility gate.
+//!
+//! Each OS has its own launch-time requirements (Windows service pack/VxKex checks, Linux
+//! TUN/`CAP_NET_ADMIN` availability, Android `VpnService` prerequisites). They're modeled
+//! behind a single [`SystemRequirement`] trait so [`get_os`] is the one place a caller needs
+//! to know about, instead of OS-specific checks scattered across entry points.
+
+#[cfg(target_os = "windows")]
+mod windows;
+
+#[cfg(target_os = "linux")]
+mod linux;
+
+#[cfg(target_os = "android")]
+mod android;
+
+#[cfg(target_os = "macos")]
+mod macos;
+
+pub struct RequirementError {
+    pub code: &'static str,
+    pub detail: Option<String>,
+}
+
+impl RequirementError {
+    fn new(code: &'static str) -> Self {
+        RequirementError { code, detail: None }
+    }
+
+    // Only `windows::check_patches` attaches a detail string today; cfg-gate this so non-Windows
+    // builds (where the `windows` module is compiled out) don't fail `-D warnings` on dead_code.
+    #[cfg(target_os = "windows")]
+    fn with_detail(code: &'static str, detail: impl Into<String>) -> Self {
+        RequirementError { code, detail: Some(detail.into()) }
+    }
+}
+
+impl std::fmt::Display for RequirementError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return match &self.detail {
+            Some(detail) => write!(f, "{}: {}", self.code, detail),
+            None => write!(f, "{}", self.code),
+        };
+    }
+}
+
+pub trait SystemRequirement {
+    fn check(&self) -> Result<(), RequirementError>;
+}
+
+pub fn get_os() -> Box<dyn SystemRequirement> {
+    #[cfg(target_os = "windows")]
+    return Box::new(windows::WindowsRequirement);
+
+    #[cfg(target_os = "linux")]
+    return Box::new(linux::LinuxRequirement);
+
+    #[cfg(target_os = "android")]
+    return Box::new(android::AndroidRequirement);
+
+    #[cfg(target_os = "macos")]
+    return Box::new(macos::MacosRequirement);
+}
+
+/// Surfaces a requirement failure to the user and terminates the process.
+#[cfg(not(target_os = "android"))]
+pub fn fail(error: RequirementError) -> ! {
+    let _ = native_dialog::DialogBuilder::message()
+        .set_level(native_dialog::MessageLevel::Error)
+        .set_title("Terracotta | 陶瓦联机")
+        .set_text(format!("陶瓦联机不支持您的系统，请与开发者联系。\n{}", error))
+        .alert()
+        .show();
+
+    std::process::exit(1);
+}
@@ -3,10 +3,9 @@ use std::marker::PhantomData;
 use std::mem::{transmute, zeroed};
 use std::os::windows::ffi::OsStrExt;
 use winapi::shared::minwindef::{FARPROC, HMODULE};
-use winapi::shared::ntdef::{UNICODE_STRING, HANDLE, PVOID, ULONG, NTSTATUS};
+use winapi::shared::ntdef::{HANDLE, NTSTATUS, PVOID, ULONG, UNICODE_STRING};
 use winapi::um::libloaderapi::{FreeLibrary, GetProcAddress, LoadLibraryW};
 use winapi::um::winnt::LPCSTR;
-use crate::win7::fail;
 
 #[allow(dead_code)]
 #[repr(C)]
@@ -61,8 +60,12 @@ pub fn kex_data_initialize() -> Option<KexProcessData> {
         if module.is_null() {
             return None;
         }
+
         let initialize: FARPROC = GetProcAddress(module, c"KexDataInitialize".as_ptr() as LPCSTR);
-        fail(!initialize.is_null(), "KEX_INCOMPLETE: VxKex 不完整");
+        if initialize.is_null() {
+            FreeLibrary(module);
+            return None;
+        }
         let initialize: unsafe extern "system" fn(*mut KexProcessData) -> NTSTATUS = transmute(initialize);
 
         let mut data: KexProcessData = zeroed();
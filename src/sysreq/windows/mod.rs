@@ -0,0 +1,91 @@
+mod kex;
+
+use super::{RequirementError, SystemRequirement};
+use kex::KexWinVerSpoof;
+use std::mem::zeroed;
+use winapi::shared::minwindef::{DWORD, WORD};
+use winapi::um::winbase::VerifyVersionInfoW;
+use winapi::um::winnt::{
+    DWORDLONG, OSVERSIONINFOEXW, VER_GREATER_EQUAL, VER_MAJORVERSION, VER_MINORVERSION,
+    VER_SERVICEPACKMAJOR, VerSetConditionMask,
+};
+use winreg::enums::HKEY_LOCAL_MACHINE;
+use winreg::RegKey;
+
+pub struct WindowsRequirement;
+
+impl SystemRequirement for WindowsRequirement {
+    fn check(&self) -> Result<(), RequirementError> {
+        if is_windows_satisfying(6, 2, 0) {
+            return Ok(());
+        }
+
+        match kex::kex_data_initialize() {
+            None => return Err(RequirementError::new("KEX_NOT_AVAILABLE")),
+            Some(kex) => {
+                if kex.ifeo_parameters.win_ver_spoof == KexWinVerSpoof::WinVerSpoofNone {
+                    return Err(RequirementError::new("KEX_WIN_VER_SPOOF"));
+                }
+                if kex.ifeo_parameters.disable_for_child == 0 {
+                    return Err(RequirementError::new("KEX_DISABLE_FOR_CHILD"));
+                }
+            }
+        }
+
+        // win7, VxKex enabled && disable_for_child
+        if !is_windows_satisfying(6, 1, 1) {
+            return Err(RequirementError::new("SYS_WIN7_SP1_NOT_AVAILABLE"));
+        }
+
+        check_patches()
+    }
+}
+
+fn check_patches() -> Result<(), RequirementError> {
+    let registry = RegKey::predef(HKEY_LOCAL_MACHINE)
+        .open_subkey(r"SOFTWARE\Microsoft\Windows\CurrentVersion\Component Based Servicing\Packages")
+        .map_err(|e| RequirementError::with_detail("SYS_REG_ERR", e.to_string()))?;
+
+    let mut patches = [("KB3063858", false), ("KB4474419", false)];
+    for sub in registry.enum_keys() {
+        let sub = sub.map_err(|e| RequirementError::with_detail("SYS_REG_ERR", e.to_string()))?;
+        for (patch, flag) in &mut patches {
+            if sub.contains(*patch) {
+                *flag = true;
+                break;
+            }
+        }
+    }
+
+    for (patch, ok) in patches {
+        if !ok {
+            return Err(RequirementError::with_detail("SYS_PATCH_NOT_AVAILABLE", patch));
+        }
+    }
+
+    return Ok(());
+}
+
+fn is_windows_satisfying(major: u8, minor: u8, sp: u8) -> bool {
+    unsafe {
+        let mut osvi = OSVERSIONINFOEXW {
+            dwOSVersionInfoSize: size_of::<OSVERSIONINFOEXW>() as DWORD,
+            dwMajorVersion: major as DWORD,
+            dwMinorVersion: minor as DWORD,
+            wServicePackMajor: sp as WORD,
+            ..zeroed()
+        };
+
+        let mut condition_mask: DWORDLONG = 0;
+        condition_mask = VerSetConditionMask(condition_mask, VER_MAJORVERSION, VER_GREATER_EQUAL);
+        condition_mask = VerSetConditionMask(condition_mask, VER_MINORVERSION, VER_GREATER_EQUAL);
+        condition_mask = VerSetConditionMask(condition_mask, VER_SERVICEPACKMAJOR, VER_GREATER_EQUAL);
+
+        let res = VerifyVersionInfoW(
+            &mut osvi,
+            VER_MAJORVERSION | VER_MINORVERSION | VER_SERVICEPACKMAJOR,
+            condition_mask,
+        );
+        res != 0
+    }
+}
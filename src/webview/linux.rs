@@ -0,0 +1,45 @@
+use webkit2gtk::{WebContext, WebView as GtkWebView, WebViewExt};
+use gtk::prelude::*;
+use gtk::{Window, WindowType};
+
+use super::WebViewAttributes;
+
+/// A handle to an open WebKitGTK view, kept around so native code can push events into the page
+/// via `eval` after `open` has handed control to the GTK main loop.
+pub(crate) struct WebView(GtkWebView);
+
+// The view is only ever touched from the GTK main thread in practice, but the handle itself is
+// just a GObject pointer that's fine to move elsewhere.
+unsafe impl Send for WebView {}
+
+impl WebView {
+    pub fn eval(&self, js: &str) {
+        self.0.run_javascript(js, gtk::gio::Cancellable::NONE, |_| {});
+    }
+}
+
+/// Opens the window described by `attrs` using WebKitGTK. The custom scheme, JS/Rust message
+/// channel, and navigation allow-list from `attrs` mirror the macOS backend's `WKWebView`
+/// feature set but aren't wired up yet here; this gets the same window/URL call site working
+/// on Linux first.
+pub(crate) fn open(attrs: WebViewAttributes, on_ready: impl FnOnce(WebView) + 'static) {
+    gtk::init().expect("Cannot initialize GTK");
+
+    let window = Window::new(WindowType::Toplevel);
+    window.set_title(&attrs.title);
+    window.set_default_size(attrs.width as i32, attrs.height as i32);
+    window.connect_delete_event(|_, _| {
+        gtk::main_quit();
+        return gtk::glib::Propagation::Stop;
+    });
+
+    let webview = GtkWebView::with_context(&WebContext::default().unwrap());
+    webview.load_uri(&attrs.url);
+    window.add(&webview);
+
+    window.show_all();
+
+    on_ready(WebView(webview));
+
+    gtk::main();
+}
@@ -0,0 +1,405 @@
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use block2::Block;
+use objc2::{
+    ClassType, class, define_class,
+    ffi::nil,
+    msg_send,
+    runtime::{AnyObject, Bool},
+};
+use objc2_app_kit::{NSBackingStoreType, NSWindowStyleMask};
+use objc2_foundation::{NSAutoreleasePool, NSObject, NSPoint, NSRect, NSSize, NSString};
+#[allow(unused_imports)]
+use objc2_web_kit::{WKWebView, WKWebViewConfiguration};
+
+use super::{FileDropEvent, FileDropPhase, WebViewAttributes};
+
+define_class!(
+    #[unsafe(super(NSObject))]
+    struct AppDelegate;
+
+    impl AppDelegate {
+        #[unsafe(method(windowWillClose:))]
+        fn window_will_close(&self, _notification: *mut AnyObject) {
+            unsafe {
+                let app: *mut AnyObject = msg_send![class!(NSApplication), sharedApplication];
+                let _: () = msg_send![app, terminate:app];
+            }
+        }
+    }
+);
+
+type SchemeCallback = Box<dyn Fn(&str) -> (Vec<u8>, String) + Send + 'static>;
+
+lazy_static::lazy_static! {
+    // define_class! doesn't give this file's manual msg_send! style a convenient place to
+    // store a Rust closure in an ivar, so each handler instance's closure lives here, keyed
+    // by the instance's own pointer.
+    static ref SCHEME_HANDLERS: Mutex<HashMap<usize, SchemeCallback>> = Mutex::new(HashMap::new());
+}
+
+define_class!(
+    #[unsafe(super(NSObject))]
+    struct SchemeHandler;
+
+    impl SchemeHandler {
+        #[unsafe(method(webView:startURLSchemeTask:))]
+        fn start_url_scheme_task(&self, _webview: *mut AnyObject, task: *mut AnyObject) {
+            unsafe {
+                let request: *mut AnyObject = msg_send![task, request];
+                let url: *mut AnyObject = msg_send![request, URL];
+                let url_string: *mut AnyObject = msg_send![url, absoluteString];
+                let path = nsstring_to_string(url_string);
+
+                let key = self as *const Self as usize;
+                let produced = SCHEME_HANDLERS.lock().unwrap().get(&key).map(|handler| handler(&path));
+                let Some((bytes, mime)) = produced else {
+                    let _: () = msg_send![task, didFinish];
+                    return;
+                };
+
+                let mime_ns = NSString::from_str(&mime);
+                let response: *mut AnyObject = msg_send![class!(NSURLResponse), alloc];
+                let response: *mut AnyObject = msg_send![
+                    response,
+                    initWithURL: url,
+                    MIMEType: &*mime_ns,
+                    expectedContentLength: bytes.len() as isize,
+                    textEncodingName: nil
+                ];
+                let _: () = msg_send![task, didReceiveResponse: response];
+
+                let data: *mut AnyObject = msg_send![class!(NSData), alloc];
+                let data: *mut AnyObject = msg_send![data, initWithBytes: bytes.as_ptr(), length: bytes.len()];
+                let _: () = msg_send![task, didReceiveData: data];
+
+                let _: () = msg_send![task, didFinish];
+            }
+        }
+
+        #[unsafe(method(webView:stopURLSchemeTask:))]
+        fn stop_url_scheme_task(&self, _webview: *mut AnyObject, _task: *mut AnyObject) {}
+    }
+);
+
+unsafe fn nsstring_to_string(ns: *mut AnyObject) -> String {
+    let c_str: *const c_char = msg_send![ns, UTF8String];
+    return CStr::from_ptr(c_str).to_string_lossy().into_owned();
+}
+
+/// A handle to a running WebView, kept around so native code can push events into the page
+/// (via [`WebView::eval`]) after [`open`] has handed control to the AppKit run loop. Also
+/// keeps the title-observer alive for as long as the webview itself is alive, and tears the
+/// KVO registration down on drop so the webview doesn't try to notify a dead observer.
+pub struct WebView {
+    webview: *mut AnyObject,
+    title_observer: *mut AnyObject,
+}
+
+// The webview is only ever touched from the main thread in practice (AppKit requires it),
+// but the handle itself is just pointer values that are fine to move to wherever it's stashed.
+unsafe impl Send for WebView {}
+
+impl WebView {
+    /// Runs `js` in the page and discards the result; fire-and-forget push from Rust to JS.
+    pub fn eval(&self, js: &str) {
+        unsafe {
+            let js_ns = NSString::from_str(js);
+            let completion_handler: *mut AnyObject = nil;
+            let _: () = msg_send![self.webview, evaluateJavaScript: &*js_ns, completionHandler: completion_handler];
+        }
+    }
+}
+
+impl Drop for WebView {
+    fn drop(&mut self) {
+        unsafe {
+            let key_path = NSString::from_str("title");
+            let _: () = msg_send![self.webview, removeObserver: self.title_observer, forKeyPath: &*key_path];
+            TITLE_OBSERVER_WINDOWS.lock().unwrap().remove(&(self.title_observer as usize));
+            FILE_DROP_HANDLERS.lock().unwrap().remove(&(self.webview as usize));
+        }
+    }
+}
+
+lazy_static::lazy_static! {
+    // Keyed by the TitleObserver instance's own pointer, same scheme as SCHEME_HANDLERS.
+    static ref TITLE_OBSERVER_WINDOWS: Mutex<HashMap<usize, usize>> = Mutex::new(HashMap::new());
+}
+
+define_class!(
+    #[unsafe(super(NSObject))]
+    struct TitleObserver;
+
+    impl TitleObserver {
+        #[unsafe(method(observeValueForKeyPath:ofObject:change:context:))]
+        fn observe_value(
+            &self,
+            _key_path: *mut AnyObject,
+            object: *mut AnyObject,
+            _change: *mut AnyObject,
+            _context: *mut AnyObject,
+        ) {
+            unsafe {
+                let key = self as *const Self as usize;
+                let Some(&window) = TITLE_OBSERVER_WINDOWS.lock().unwrap().get(&key) else { return; };
+
+                let title: *mut AnyObject = msg_send![object, title];
+                let _: () = msg_send![window as *mut AnyObject, setTitle: title];
+            }
+        }
+    }
+);
+
+type MessageCallback = Box<dyn Fn(String) + Send + 'static>;
+
+lazy_static::lazy_static! {
+    static ref MESSAGE_HANDLERS: Mutex<HashMap<usize, MessageCallback>> = Mutex::new(HashMap::new());
+}
+
+define_class!(
+    #[unsafe(super(NSObject))]
+    struct ScriptMessageHandler;
+
+    impl ScriptMessageHandler {
+        #[unsafe(method(userContentController:didReceiveScriptMessage:))]
+        fn did_receive_script_message(&self, _controller: *mut AnyObject, message: *mut AnyObject) {
+            unsafe {
+                let body: *mut AnyObject = msg_send![message, body];
+                let body = nsstring_to_string(body);
+
+                let key = self as *const Self as usize;
+                if let Some(callback) = MESSAGE_HANDLERS.lock().unwrap().get(&key) {
+                    callback(body);
+                }
+            }
+        }
+    }
+);
+
+// WKNavigationActionPolicy is backed by NSInteger.
+type NSInteger = isize;
+const WK_NAVIGATION_ACTION_POLICY_CANCEL: NSInteger = 0;
+const WK_NAVIGATION_ACTION_POLICY_ALLOW: NSInteger = 1;
+
+type AllowPredicate = Box<dyn Fn(&str, &str) -> bool + Send + 'static>;
+
+lazy_static::lazy_static! {
+    static ref NAVIGATION_POLICIES: Mutex<HashMap<usize, AllowPredicate>> = Mutex::new(HashMap::new());
+}
+
+define_class!(
+    #[unsafe(super(NSObject))]
+    struct NavigationDelegate;
+
+    impl NavigationDelegate {
+        #[unsafe(method(webView:decidePolicyForNavigationAction:decisionHandler:))]
+        fn decide_policy(
+            &self,
+            _webview: *mut AnyObject,
+            navigation_action: *mut AnyObject,
+            decision_handler: &Block<dyn Fn(NSInteger)>,
+        ) {
+            unsafe {
+                let request: *mut AnyObject = msg_send![navigation_action, request];
+                let url: *mut AnyObject = msg_send![request, URL];
+                let scheme_ns: *mut AnyObject = msg_send![url, scheme];
+                let host_ns: *mut AnyObject = msg_send![url, host];
+                let scheme = if scheme_ns.is_null() { String::new() } else { nsstring_to_string(scheme_ns) };
+                let host = if host_ns.is_null() { String::new() } else { nsstring_to_string(host_ns) };
+
+                let key = self as *const Self as usize;
+                let allowed = NAVIGATION_POLICIES.lock().unwrap()
+                    .get(&key)
+                    .is_some_and(|allow| allow(&scheme, &host));
+
+                if allowed {
+                    decision_handler.call((WK_NAVIGATION_ACTION_POLICY_ALLOW,));
+                } else {
+                    decision_handler.call((WK_NAVIGATION_ACTION_POLICY_CANCEL,));
+
+                    let workspace: *mut AnyObject = msg_send![class!(NSWorkspace), sharedWorkspace];
+                    let _: Bool = msg_send![workspace, openURL: url];
+                }
+            }
+        }
+    }
+);
+
+// NSDragOperation is backed by NSUInteger.
+type NSUInteger = u64;
+const NS_DRAG_OPERATION_COPY: NSUInteger = 1;
+
+lazy_static::lazy_static! {
+    static ref FILE_DROP_HANDLERS: Mutex<HashMap<usize, Box<dyn Fn(FileDropEvent) + Send + 'static>>> = Mutex::new(HashMap::new());
+}
+
+unsafe fn paths_from_dragging_info(sender: *mut AnyObject) -> Vec<PathBuf> {
+    let pasteboard: *mut AnyObject = msg_send![sender, draggingPasteboard];
+    let filenames_type = NSString::from_str("NSFilenamesPboardType");
+    let filenames: *mut AnyObject = msg_send![pasteboard, propertyListForType: &*filenames_type];
+    if filenames.is_null() {
+        return vec![];
+    }
+
+    let count: usize = msg_send![filenames, count];
+    return (0..count)
+        .map(|index| {
+            let path: *mut AnyObject = msg_send![filenames, objectAtIndex: index];
+            PathBuf::from(nsstring_to_string(path))
+        })
+        .collect();
+}
+
+fn dispatch_file_drop<T: ClassType>(instance: &T, phase: FileDropPhase, paths: Vec<PathBuf>) {
+    let key = instance as *const T as usize;
+    if let Some(handler) = FILE_DROP_HANDLERS.lock().unwrap().get(&key) {
+        handler(FileDropEvent { phase, paths });
+    }
+}
+
+// Subclassing WKWebView (rather than a plain NSObject) is what lets us override the dragging
+// methods AppKit calls directly on the view that `registerForDraggedTypes:` was sent to.
+define_class!(
+    #[unsafe(super(WKWebView))]
+    struct DropWebView;
+
+    impl DropWebView {
+        #[unsafe(method(draggingEntered:))]
+        fn dragging_entered(&self, sender: *mut AnyObject) -> NSUInteger {
+            unsafe {
+                dispatch_file_drop(self, FileDropPhase::Enter, paths_from_dragging_info(sender));
+            }
+            return NS_DRAG_OPERATION_COPY;
+        }
+
+        #[unsafe(method(draggingExited:))]
+        fn dragging_exited(&self, _sender: *mut AnyObject) {
+            dispatch_file_drop(self, FileDropPhase::Leave, vec![]);
+        }
+
+        #[unsafe(method(performDragOperation:))]
+        fn perform_drag_operation(&self, sender: *mut AnyObject) -> Bool {
+            unsafe {
+                dispatch_file_drop(self, FileDropPhase::Drop, paths_from_dragging_info(sender));
+            }
+            return Bool::YES;
+        }
+    }
+);
+
+/// Opens the window described by `attrs` using WKWebView. `on_ready` is handed the [`WebView`]
+/// just before the AppKit run loop takes over, so callers can stash it (e.g. in a `static`) to
+/// push events back into the page later via `eval`.
+pub(crate) fn open(attrs: WebViewAttributes, on_ready: impl FnOnce(WebView) + 'static) {
+    unsafe {
+        let _pool = NSAutoreleasePool::new();
+
+        let app: *mut AnyObject = msg_send![class!(NSApplication), sharedApplication];
+        let _: Bool = msg_send![app, setActivationPolicy: 0i64];
+
+        // Setup Window
+        let window: *mut AnyObject = msg_send![class!(NSWindow), alloc];
+        let frame = NSRect::new(NSPoint::new(0., 0.), NSSize::new(attrs.width, attrs.height));
+        let window: *mut AnyObject = msg_send![
+            window,
+            initWithContentRect: frame,
+            styleMask: NSWindowStyleMask::Resizable | NSWindowStyleMask::Titled | NSWindowStyleMask::Closable,
+            backing: NSBackingStoreType::Buffered,
+            defer: Bool::NO
+        ];
+
+        let _: () = msg_send![window, setTitle: &*NSString::from_str(&attrs.title)];
+
+        // Setup WebView, with the custom scheme (if any) and a JS -> Rust message channel
+        // registered on its configuration
+        let config: *mut AnyObject = msg_send![class!(WKWebViewConfiguration), new];
+
+        let custom_scheme = attrs.scheme.as_ref().map(|(scheme, _)| scheme.clone());
+
+        if let Some((scheme, handler)) = attrs.scheme {
+            let scheme_handler: *mut AnyObject = msg_send![SchemeHandler::class(), new];
+            SCHEME_HANDLERS.lock().unwrap().insert(scheme_handler as usize, handler);
+            let scheme_ns = NSString::from_str(&scheme);
+            let _: () = msg_send![config, setURLSchemeHandler: scheme_handler, forURLScheme: &*scheme_ns];
+        }
+
+        let message_handler: *mut AnyObject = msg_send![ScriptMessageHandler::class(), new];
+        MESSAGE_HANDLERS.lock().unwrap().insert(
+            message_handler as usize,
+            attrs.on_message.unwrap_or_else(|| Box::new(|_| {})),
+        );
+        let user_content_controller: *mut AnyObject = msg_send![config, userContentController];
+        let handler_name = NSString::from_str("terracotta");
+        let _: () = msg_send![user_content_controller, addScriptMessageHandler: message_handler, name: &*handler_name];
+
+        let webview: *mut AnyObject = msg_send![DropWebView::class(), alloc];
+        let webview: *mut AnyObject = msg_send![webview, initWithFrame:frame, configuration:config];
+
+        // Report dropped files (e.g. a world folder or config file) to on_file_drop
+        if let Some(on_file_drop) = attrs.on_file_drop {
+            FILE_DROP_HANDLERS.lock().unwrap().insert(webview as usize, on_file_drop);
+            let filenames_type = NSString::from_str("NSFilenamesPboardType");
+            let dragged_types: *mut AnyObject = msg_send![class!(NSArray), arrayWithObject: &*filenames_type];
+            let _: () = msg_send![webview, registerForDraggedTypes: dragged_types];
+        }
+
+        // Load URL
+        let url_str = NSString::from_str(&attrs.url);
+        let url: *mut AnyObject = msg_send![class!(NSURL), URLWithString:&*url_str];
+        let request: *mut AnyObject = msg_send![class!(NSURLRequest), requestWithURL:url];
+        let _: *mut AnyObject= msg_send![webview, loadRequest:request];
+
+        // Bind WebView to window
+        let content_view: *mut AnyObject = msg_send![window, contentView];
+        let _: () = msg_send![content_view, addSubview:webview];
+        let _: () = msg_send![webview, setAutoresizingMask: 18u64];
+
+        // Keep the window title in sync with document.title
+        let title_observer: *mut AnyObject = msg_send![TitleObserver::class(), new];
+        TITLE_OBSERVER_WINDOWS.lock().unwrap().insert(title_observer as usize, window as usize);
+        let title_key_path = NSString::from_str("title");
+        let _: () = msg_send![
+            webview,
+            addObserver: title_observer,
+            forKeyPath: &*title_key_path,
+            options: 1u64, // NSKeyValueObservingOptionNew
+            context: nil
+        ];
+
+        // Restrict in-app navigation to the allow-list; everything else opens in the real browser.
+        // The default covers the app's own origin: its custom scheme (if registered), and the
+        // loopback HTTP control server (`http(s)://127.0.0.1:<port>/`, `::1`, `localhost`) that
+        // `open` is typically pointed at.
+        let navigation_delegate: *mut AnyObject = msg_send![NavigationDelegate::class(), new];
+        NAVIGATION_POLICIES.lock().unwrap().insert(
+            navigation_delegate as usize,
+            attrs.allow_host.unwrap_or_else(|| {
+                Box::new(move |scheme, host| {
+                    custom_scheme.as_deref() == Some(scheme)
+                        || host.is_empty()
+                        || host == "localhost"
+                        || host == "127.0.0.1"
+                        || host == "::1"
+                })
+            }),
+        );
+        let _: () = msg_send![webview, setNavigationDelegate: navigation_delegate];
+
+        // Delegate for window close
+        let delegate: *mut AnyObject = msg_send![AppDelegate::class(), new];
+        let _: () = msg_send![window, setDelegate:delegate];
+
+        let _: () = msg_send![window, makeKeyAndOrderFront:nil];
+        let _: () = msg_send![app, activateIgnoringOtherApps:Bool::YES];
+
+        on_ready(WebView { webview, title_observer });
+
+        // Run the app
+        let _: () = msg_send![app, run];
+    }
+}
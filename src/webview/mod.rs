@@ -0,0 +1,141 @@
+//! Cross-platform embedded browser window for the desktop LAN-hosting UI.
+//!
+//! [`WebViewWindow`] is a platform-neutral builder (title, size, URL, and the IPC/custom-scheme
+//! hooks) that dispatches to a per-OS backend: WKWebView on macOS, WebView2 on Windows, and
+//! WebKitGTK on Linux. This mirrors how [`sysreq`](crate::sysreq) keeps the one OS-specific
+//! decision point (which backend to use) out of call sites.
+
+#[cfg(target_os = "macos")]
+mod macos;
+
+#[cfg(target_os = "windows")]
+mod windows;
+
+#[cfg(target_os = "linux")]
+mod linux;
+
+use std::path::PathBuf;
+
+type SchemeHandler = Box<dyn Fn(&str) -> (Vec<u8>, String) + Send + 'static>;
+type MessageHandler = Box<dyn Fn(String) + Send + 'static>;
+type AllowPredicate = Box<dyn Fn(&str, &str) -> bool + Send + 'static>;
+type FileDropHandler = Box<dyn Fn(FileDropEvent) + Send + 'static>;
+
+/// Which phase of a drag a [`FileDropEvent`] reports.
+pub enum FileDropPhase {
+    Enter,
+    Drop,
+    Leave,
+}
+
+/// Reported to a window's `on_file_drop` hook as the user drags files over the webview (e.g. a
+/// Minecraft world folder or a config file) and drops them.
+pub struct FileDropEvent {
+    pub phase: FileDropPhase,
+    pub paths: Vec<PathBuf>,
+}
+
+#[derive(Default)]
+pub struct WebViewAttributes {
+    title: String,
+    width: f64,
+    height: f64,
+    url: String,
+    scheme: Option<(String, SchemeHandler)>,
+    on_message: Option<MessageHandler>,
+    allow_host: Option<AllowPredicate>,
+    on_file_drop: Option<FileDropHandler>,
+}
+
+/// A handle to an open window, kept around so native code can push events into the page (via
+/// [`WebView::eval`]) after [`WebViewWindow::open`] has handed control to the platform's event
+/// loop.
+pub struct WebView(PlatformWebView);
+
+impl WebView {
+    pub fn eval(&self, js: &str) {
+        self.0.eval(js);
+    }
+}
+
+#[cfg(target_os = "macos")]
+type PlatformWebView = macos::WebView;
+
+#[cfg(target_os = "windows")]
+type PlatformWebView = windows::WebView;
+
+#[cfg(target_os = "linux")]
+type PlatformWebView = linux::WebView;
+
+/// Builder for the desktop LAN-hosting UI window. The same call site produces a WKWebView
+/// window on macOS, a WebView2 window on Windows, or a WebKitGTK window on Linux.
+pub struct WebViewWindow {
+    attributes: WebViewAttributes,
+}
+
+impl WebViewWindow {
+    pub fn new(url: impl Into<String>) -> Self {
+        WebViewWindow {
+            attributes: WebViewAttributes {
+                title: "Terracotta | 陶瓦联机".into(),
+                width: 1000.,
+                height: 700.,
+                url: url.into(),
+                ..Default::default()
+            },
+        }
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.attributes.title = title.into();
+        return self;
+    }
+
+    pub fn size(mut self, width: f64, height: f64) -> Self {
+        self.attributes.width = width;
+        self.attributes.height = height;
+        return self;
+    }
+
+    /// Serves `scheme` (e.g. `"terracotta"`) directly from memory/disk via `handler` instead of
+    /// loading the frontend from a real HTTP server. `handler` is given the requested URL and
+    /// returns the response body together with its MIME type.
+    pub fn scheme(mut self, scheme: impl Into<String>, handler: impl Fn(&str) -> (Vec<u8>, String) + Send + 'static) -> Self {
+        self.attributes.scheme = Some((scheme.into(), Box::new(handler)));
+        return self;
+    }
+
+    /// Invoked with the JSON string body of every message the page posts to the native side.
+    pub fn on_message(mut self, handler: impl Fn(String) + Send + 'static) -> Self {
+        self.attributes.on_message = Some(Box::new(handler));
+        return self;
+    }
+
+    /// Consulted on every navigation with `(scheme, host)`; returning `false` cancels it inside
+    /// the webview and opens it in the system browser instead (e.g. for docs/GitHub links).
+    pub fn allow_host(mut self, predicate: impl Fn(&str, &str) -> bool + Send + 'static) -> Self {
+        self.attributes.allow_host = Some(Box::new(predicate));
+        return self;
+    }
+
+    /// Invoked as the user drags files over the webview and drops them (e.g. a Minecraft world
+    /// folder or a config file), so the frontend can react — e.g. load a dropped save.
+    pub fn on_file_drop(mut self, handler: impl Fn(FileDropEvent) + Send + 'static) -> Self {
+        self.attributes.on_file_drop = Some(Box::new(handler));
+        return self;
+    }
+
+    /// Opens the window and blocks for the lifetime of the platform event loop. `on_ready` is
+    /// handed the [`WebView`] just before control is handed over, so callers can stash it
+    /// (e.g. in a `static`) to push events into the page later via `eval`.
+    pub fn open(self, on_ready: impl FnOnce(WebView) + 'static) {
+        #[cfg(target_os = "macos")]
+        macos::open(self.attributes, |webview| on_ready(WebView(webview)));
+
+        #[cfg(target_os = "windows")]
+        windows::open(self.attributes, |webview| on_ready(WebView(webview)));
+
+        #[cfg(target_os = "linux")]
+        linux::open(self.attributes, |webview| on_ready(WebView(webview)));
+    }
+}
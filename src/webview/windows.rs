@@ -0,0 +1,112 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use webview2_com::Microsoft::Web::WebView2::Win32::{
+    CreateCoreWebView2EnvironmentWithOptions, ICoreWebView2Controller,
+};
+use webview2_com::CreateCoreWebView2EnvironmentCompletedHandler;
+use windows::core::HSTRING;
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, RECT, WPARAM};
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DispatchMessageW, GetClientRect, GetMessageW,
+    PostQuitMessage, RegisterClassW, ShowWindow, TranslateMessage, CW_USEDEFAULT, MSG,
+    SW_SHOW, WM_DESTROY, WNDCLASSW, WS_OVERLAPPEDWINDOW,
+};
+
+use super::WebViewAttributes;
+
+/// A handle to an open WebView2 control, kept around so native code can push events into the
+/// page via `eval` after `open` has handed control to the Win32 message loop.
+pub(crate) struct WebView(ICoreWebView2Controller);
+
+// The controller is only ever touched from the thread that created it in practice (WebView2
+// requires this), but the handle itself is just a COM pointer that's fine to move elsewhere.
+unsafe impl Send for WebView {}
+
+impl WebView {
+    pub fn eval(&self, js: &str) {
+        if let Ok(webview) = self.0.CoreWebView2() {
+            let _ = webview.ExecuteScript(&HSTRING::from(js), &webview2_com::ExecuteScriptCompletedHandler::create(
+                Box::new(|_, _| Ok(())),
+            ));
+        }
+    }
+}
+
+unsafe extern "system" fn window_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if msg == WM_DESTROY {
+        PostQuitMessage(0);
+        return LRESULT(0);
+    }
+    return DefWindowProcW(hwnd, msg, wparam, lparam);
+}
+
+/// Opens the window described by `attrs` using WebView2. The custom scheme, JS/Rust message
+/// channel, and navigation allow-list from `attrs` mirror the macOS backend's `WKWebView`
+/// feature set but aren't wired up yet here; this gets the same window/URL call site working
+/// on Windows first.
+pub(crate) fn open(attrs: WebViewAttributes, on_ready: impl FnOnce(WebView) + 'static) {
+    unsafe {
+        let class_name = HSTRING::from("TerracottaWebViewWindow");
+        let wndclass = WNDCLASSW {
+            lpfnWndProc: Some(window_proc),
+            lpszClassName: windows::core::PCWSTR(class_name.as_ptr()),
+            ..Default::default()
+        };
+        RegisterClassW(&wndclass);
+
+        let hwnd = CreateWindowExW(
+            Default::default(),
+            &class_name,
+            &HSTRING::from(attrs.title.as_str()),
+            WS_OVERLAPPEDWINDOW,
+            CW_USEDEFAULT,
+            CW_USEDEFAULT,
+            attrs.width as i32,
+            attrs.height as i32,
+            None,
+            None,
+            None,
+            None,
+        ).unwrap();
+
+        ShowWindow(hwnd, SW_SHOW);
+
+        let url = attrs.url.clone();
+        let on_ready = Rc::new(RefCell::new(Some(on_ready)));
+
+        CreateCoreWebView2EnvironmentWithOptions(
+            None,
+            None,
+            None,
+            &CreateCoreWebView2EnvironmentCompletedHandler::create(Box::new(move |_, environment| {
+                let environment = environment.unwrap();
+                environment.CreateCoreWebView2Controller(
+                    hwnd,
+                    &webview2_com::CreateCoreWebView2ControllerCompletedHandler::create(Box::new(move |_, created| {
+                        let created = created.unwrap();
+
+                        let mut bounds = RECT::default();
+                        GetClientRect(hwnd, &mut bounds).ok();
+                        created.SetBounds(bounds).ok();
+
+                        if let Ok(webview) = created.CoreWebView2() {
+                            webview.Navigate(&HSTRING::from(url.as_str())).ok();
+                        }
+
+                        if let Some(on_ready) = on_ready.borrow_mut().take() {
+                            on_ready(WebView(created));
+                        }
+                        Ok(())
+                    })),
+                )
+            })),
+        ).ok();
+
+        let mut msg = MSG::default();
+        while GetMessageW(&mut msg, None, 0, 0).into() {
+            let _ = TranslateMessage(&msg);
+            DispatchMessageW(&msg);
+        }
+    }
+}